@@ -0,0 +1,83 @@
+use std::env::var;
+use std::path::PathBuf;
+
+use git2::{Config, Cred, CredentialType, RemoteCallbacks};
+use log::debug;
+
+pub(crate) fn build_callbacks<'a>(remote_url: &str) -> RemoteCallbacks<'a> {
+    let is_https = remote_url.starts_with("http://") || remote_url.starts_with("https://");
+
+    let mut callbacks = RemoteCallbacks::new();
+
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        if is_https {
+            return https_credentials(url, allowed_types);
+        }
+
+        ssh_credentials(username_from_url, allowed_types)
+    });
+
+    callbacks
+}
+
+fn ssh_credentials(
+    username_from_url: Option<&str>,
+    allowed_types: CredentialType,
+) -> Result<Cred, git2::Error> {
+    let username = username_from_url.unwrap_or("git");
+
+    if allowed_types.contains(CredentialType::SSH_KEY) {
+        if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+            debug!("Authenticating {username} via ssh-agent");
+            return Ok(cred);
+        }
+
+        for key_path in ssh_key_paths() {
+            if !key_path.exists() {
+                continue;
+            }
+
+            if let Ok(cred) = Cred::ssh_key(username, None, &key_path, None) {
+                debug!("Authenticating {username} with key {}", key_path.display());
+                return Ok(cred);
+            }
+        }
+    }
+
+    Err(git2::Error::from_str(
+        "No usable SSH credentials found (tried ssh-agent and configured key paths)",
+    ))
+}
+
+fn https_credentials(url: &str, allowed_types: CredentialType) -> Result<Cred, git2::Error> {
+    if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        if let Ok(token) = var("REBASER_HTTPS_TOKEN").or_else(|_| var("GITHUB_TOKEN")) {
+            return Cred::userpass_plaintext(&token, "");
+        }
+
+        if let Ok(config) = Config::open_default() {
+            if let Ok(cred) = Cred::credential_helper(&config, url, None) {
+                debug!("Authenticating {url} via git credential helper");
+                return Ok(cred);
+            }
+        }
+    }
+
+    Err(git2::Error::from_str(&format!(
+        "No usable HTTPS credentials found for {url} (set REBASER_HTTPS_TOKEN or configure a git credential helper)"
+    )))
+}
+
+fn ssh_key_paths() -> Vec<PathBuf> {
+    let home = var("HOME").unwrap();
+
+    if let Ok(explicit) = var("REBASER_SSH_KEY") {
+        return vec![PathBuf::from(explicit)];
+    }
+
+    vec![
+        PathBuf::from(format!("{home}/.ssh/id_ed25519")),
+        PathBuf::from(format!("{home}/.ssh/id_rsa")),
+        PathBuf::from(format!("{home}/.ssh/id_ecdsa")),
+    ]
+}
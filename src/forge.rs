@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+
+#[cfg(feature = "forgejo")]
+use crate::forgejo::ForgejoClient;
+#[cfg(feature = "github")]
+use crate::github::GithubClient;
+
+pub(crate) struct ForgeRef {
+    pub(crate) ref_field: String,
+}
+
+pub(crate) struct ForgePullRequest {
+    pub(crate) title: String,
+    pub(crate) author: String,
+    pub(crate) head: ForgeRef,
+    pub(crate) base: ForgeRef,
+}
+
+pub(crate) struct ForgeRepo {
+    pub(crate) default_branch: String,
+}
+
+#[async_trait]
+pub(crate) trait Forge {
+    async fn get_repo(&self, owner: &str, repo: &str) -> ForgeRepo;
+
+    async fn get_all_open_prs(&self, owner: &str, repo: &str) -> Vec<ForgePullRequest>;
+
+    async fn get_current_user(&self) -> String;
+}
+
+pub(crate) fn forge_for_host(host: &str) -> Box<dyn Forge> {
+    #[cfg(any(feature = "github", feature = "forgejo"))]
+    let is_github = host == "github.com" || host.contains("github");
+
+    #[cfg(feature = "github")]
+    if is_github {
+        return Box::new(GithubClient::new(host));
+    }
+
+    #[cfg(feature = "forgejo")]
+    if !is_github {
+        return Box::new(ForgejoClient::new(host));
+    }
+
+    panic!(
+        "No forge backend compiled in for host {host}; enable the `github` or `forgejo` feature"
+    )
+}
@@ -0,0 +1,134 @@
+use std::env::var;
+use std::fs;
+
+use async_trait::async_trait;
+use log::debug;
+use reqwest::Client;
+use serde::Deserialize;
+use toml::Value;
+
+use crate::forge::{Forge, ForgePullRequest, ForgeRef, ForgeRepo};
+
+pub(crate) struct ForgejoClient {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl ForgejoClient {
+    pub(crate) fn new(host: &str) -> ForgejoClient {
+        ForgejoClient {
+            client: Client::new(),
+            base_url: format!("https://{host}/api/v1"),
+            token: get_oauth_token(host),
+        }
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> T {
+        self.client
+            .get(format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap()
+            .json::<T>()
+            .await
+            .unwrap()
+    }
+}
+
+fn get_oauth_token(host: &str) -> String {
+    let filename = format!("{}/.forgejo", var("HOME").unwrap());
+
+    let config = fs::read_to_string(&filename)
+        .unwrap_or_else(|_| panic!("File {filename} is missing. Create a token at https://{host}/user/settings/applications"))
+        .parse::<Value>()
+        .unwrap_or_else(|_| panic!("Error parsing {filename}"));
+
+    let config_table = config
+        .as_table()
+        .unwrap_or_else(|| panic!("Error parsing {filename}"));
+
+    let host_table = config_table
+        .get(host)
+        .unwrap_or_else(|| panic!("{host} table missing from {filename}"))
+        .as_table()
+        .unwrap_or_else(|| panic!("Error parsing table {host} from {filename}"));
+
+    host_table
+        .get("token")
+        .unwrap_or_else(|| panic!("Missing token key for {host} in {filename}"))
+        .as_str()
+        .unwrap_or_else(|| panic!("Expected string for token key under {host} in {filename}"))
+        .to_owned()
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    default_branch: String,
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequestRef {
+    #[serde(rename = "ref")]
+    ref_field: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequest {
+    title: String,
+    user: User,
+    head: PullRequestRef,
+    base: PullRequestRef,
+}
+
+#[async_trait]
+impl Forge for ForgejoClient {
+    async fn get_repo(&self, owner: &str, repo: &str) -> ForgeRepo {
+        let repository: Repository = self.get(&format!("/repos/{owner}/{repo}")).await;
+
+        ForgeRepo {
+            default_branch: repository.default_branch,
+        }
+    }
+
+    async fn get_all_open_prs(&self, owner: &str, repo: &str) -> Vec<ForgePullRequest> {
+        let mut prs: Vec<PullRequest> = Vec::new();
+        for page in 1.. {
+            let mut page_prs: Vec<PullRequest> = self
+                .get(&format!("/repos/{owner}/{repo}/pulls?state=open&page={page}"))
+                .await;
+            if page_prs.is_empty() {
+                break;
+            }
+            prs.append(&mut page_prs);
+        }
+
+        debug!("Forgejo PRs: {}", prs.len());
+
+        prs.into_iter()
+            .map(|pr| ForgePullRequest {
+                title: pr.title,
+                author: pr.user.login,
+                head: ForgeRef {
+                    ref_field: pr.head.ref_field,
+                },
+                base: ForgeRef {
+                    ref_field: pr.base.ref_field,
+                },
+            })
+            .collect()
+    }
+
+    async fn get_current_user(&self) -> String {
+        let user: User = self.get("/user").await;
+        user.login
+    }
+}
@@ -1,23 +1,47 @@
+use std::cell::Cell;
+use std::env::var;
 use std::process::Command;
 
 use git2::BranchType::Local;
 
-use git2::{Reference, Remote, Repository};
+use git2::{
+    AutotagOption, FetchOptions, Oid, PushOptions, Reference, Remote, Repository,
+    SubmoduleUpdateOptions,
+};
 use log::{debug, error, info};
-use octocrab::models::pulls::PullRequest;
+
+use crate::credentials::build_callbacks;
+use crate::forge::ForgePullRequest;
+
+pub(crate) enum RebaseOutcome {
+    Success,
+    Conflict { commit: Oid, paths: Vec<String> },
+}
 
 pub(crate) trait RepositoryOps {
-    fn rebase(&self, pr: &PullRequest) -> bool;
+    fn rebase(&self, head: &str, base: &str, title: &str) -> RebaseOutcome;
 
     fn get_origin_remote(&self) -> Remote<'_>;
 
     fn fast_forward(&self, refname: &str);
 
-    fn is_safe_pr(&self, pr: &PullRequest) -> bool;
+    fn is_safe_pr(&self, pr: &ForgePullRequest) -> bool;
 
     fn check_linear_history(&self, branch: &str);
 
     fn get_remote_for_branch(&self, branch: &str) -> Remote<'_>;
+
+    async fn fetch_all_remotes(&self);
+
+    fn update_submodules(&self);
+
+    fn expected_remote_oid(&self, branch: &str) -> Oid;
+
+    fn local_branch_oid(&self, branch: &str) -> Oid;
+
+    fn is_ancestor(&self, ancestor: &str, branch: &str) -> bool;
+
+    fn push_with_lease(&self, remote_name: &str, branch: &str, expected_old_oid: Oid) -> bool;
 }
 
 pub(crate) struct GitRepository {
@@ -98,39 +122,57 @@ impl Drop for GitRepository {
 }
 
 impl RepositoryOps for GitRepository {
-    fn rebase(&self, pr: &PullRequest) -> bool {
-        let head = &pr.head.ref_field;
-        let base = &pr.base.ref_field;
-
-        let pr_title = pr.title.as_ref().unwrap();
-
+    fn rebase(&self, head: &str, base: &str, title: &str) -> RebaseOutcome {
         info!(
-            "Rebasing \"{pr_title}\" {base} (remote {}) <- {head} (remote {})...",
+            "Rebasing \"{title}\" {base} (remote {}) <- {head} (remote {})...",
             self.get_remote_for_branch(base).name().unwrap(),
             self.get_remote_for_branch(head).name().unwrap()
         );
 
-        let output = Command::new("git")
-            .arg("rebase")
-            .arg(base)
-            .arg(head)
-            .output()
+        let head_branch = self.repository.find_branch(head, Local).unwrap();
+        let base_branch = self.repository.find_branch(base, Local).unwrap();
+
+        let head_commit = self
+            .repository
+            .reference_to_annotated_commit(head_branch.get())
+            .unwrap();
+        let upstream_commit = self
+            .repository
+            .reference_to_annotated_commit(base_branch.get())
             .unwrap();
 
-        if !output.status.success() {
-            error!("Error rebasing {head} onto {base}. Aborting...");
+        let mut rebase = self
+            .repository
+            .rebase(Some(&head_commit), Some(&upstream_commit), None, None)
+            .unwrap();
 
-            assert!(Command::new("git")
-                .arg("rebase")
-                .arg("--abort")
-                .status()
-                .expect("git rebase --abort should not fail")
-                .success());
+        let signature = self.repository.signature().unwrap();
 
-            return false;
+        while let Some(operation) = rebase.next() {
+            let operation = operation.unwrap();
+
+            if self.repository.index().unwrap().has_conflicts() {
+                let paths = conflicted_paths(&self.repository);
+
+                error!(
+                    "Conflict rebasing {head} onto {base} at commit {}. Aborting...",
+                    operation.id()
+                );
+
+                rebase.abort().unwrap();
+
+                return RebaseOutcome::Conflict {
+                    commit: operation.id(),
+                    paths,
+                };
+            }
+
+            rebase.commit(None, &signature, None).unwrap();
         }
 
-        true
+        rebase.finish(Some(&signature)).unwrap();
+
+        RebaseOutcome::Success
     }
 
     fn get_origin_remote(&self) -> Remote<'_> {
@@ -148,7 +190,7 @@ impl RepositoryOps for GitRepository {
             .success());
     }
 
-    fn is_safe_pr(&self, pr: &PullRequest) -> bool {
+    fn is_safe_pr(&self, pr: &ForgePullRequest) -> bool {
         let base = &pr.base.ref_field;
 
         let local_base_branch = match self.repository.find_branch(base, Local) {
@@ -161,7 +203,7 @@ impl RepositoryOps for GitRepository {
 
         let local_base_ref = local_base_branch.get();
 
-        let pr_title = pr.title.as_ref().unwrap();
+        let pr_title = &pr.title;
 
         if local_base_ref != local_base_branch.upstream().unwrap().get() {
             debug!("Pr \"{pr_title}\" is not safe because base ref \"{base}\" is not safe");
@@ -258,4 +300,163 @@ impl RepositoryOps for GitRepository {
 
         remote
     }
+
+    async fn fetch_all_remotes(&self) {
+        let remote_names: Vec<String> = self
+            .repository
+            .remotes()
+            .unwrap()
+            .iter()
+            .flatten()
+            .map(str::to_string)
+            .collect();
+
+        let tasks: Vec<_> = remote_names
+            .into_iter()
+            .map(|remote_name| tokio::task::spawn_blocking(move || fetch_remote(&remote_name)))
+            .collect();
+
+        for task in tasks {
+            task.await.expect("fetch task should not panic");
+        }
+    }
+
+    fn update_submodules(&self) {
+        if var("REBASER_UPDATE_SUBMODULES").is_err() {
+            return;
+        }
+
+        for mut submodule in self.repository.submodules().unwrap() {
+            let name = submodule.name().unwrap_or("<unknown>").to_string();
+
+            info!("Updating submodule {name}...");
+
+            let mut fetch_options = FetchOptions::new();
+            fetch_options.remote_callbacks(build_callbacks(submodule.url().unwrap_or_default()));
+
+            let mut update_options = SubmoduleUpdateOptions::new();
+            update_options.fetch(fetch_options);
+
+            submodule
+                .update(true, Some(&mut update_options))
+                .unwrap_or_else(|e| panic!("Updating submodule {name} should not fail: {e}"));
+        }
+    }
+
+    fn expected_remote_oid(&self, branch: &str) -> Oid {
+        let local_branch = self.repository.find_branch(branch, Local).unwrap();
+        let upstream = local_branch.upstream().unwrap();
+
+        upstream.get().peel_to_commit().unwrap().id()
+    }
+
+    fn local_branch_oid(&self, branch: &str) -> Oid {
+        self.repository
+            .find_branch(branch, Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+    }
+
+    fn is_ancestor(&self, ancestor: &str, branch: &str) -> bool {
+        let ancestor_oid = self.local_branch_oid(ancestor);
+        let branch_oid = self.local_branch_oid(branch);
+
+        ancestor_oid == branch_oid
+            || self
+                .repository
+                .graph_descendant_of(branch_oid, ancestor_oid)
+                .unwrap()
+    }
+
+    fn push_with_lease(&self, remote_name: &str, branch: &str, expected_old_oid: Oid) -> bool {
+        let mut remote = self.repository.find_remote(remote_name).unwrap();
+
+        let remote_url = remote.url().unwrap().to_string();
+        let refname = format!("refs/heads/{branch}");
+
+        let lease_failed = Cell::new(false);
+
+        let mut callbacks = build_callbacks(&remote_url);
+        callbacks.push_negotiation(|updates| {
+            let current_remote_oid = updates
+                .iter()
+                .find(|update| update.dst_refname() == Some(refname.as_str()))
+                .map(git2::PushUpdate::src);
+
+            if current_remote_oid != Some(expected_old_oid) {
+                lease_failed.set(true);
+                return Err(git2::Error::from_str(
+                    "force-with-lease check failed: remote ref moved since the last fetch",
+                ));
+            }
+
+            Ok(())
+        });
+
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+
+        match remote.push(&[format!("+{refname}:{refname}")], Some(&mut push_options)) {
+            Ok(()) => {
+                info!("Successfully pushed {branch} to {remote_name}");
+                true
+            }
+            Err(_) if lease_failed.get() => {
+                error!(
+                    "Remote {remote_name} ref {refname} moved since the last fetch (force-with-lease check failed). Not pushing."
+                );
+                false
+            }
+            Err(e) => {
+                error!("Push to {remote_name} failed for {branch}: {e}");
+                false
+            }
+        }
+    }
+}
+
+fn fetch_remote(remote_name: &str) {
+    let repository = Repository::discover(".").unwrap();
+    let mut remote = repository.find_remote(remote_name).unwrap();
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options
+        .remote_callbacks(build_callbacks(remote.url().unwrap()))
+        .download_tags(AutotagOption::All);
+
+    info!("Fetching remote {remote_name}...");
+
+    remote
+        .fetch(
+            &[format!("+refs/heads/*:refs/remotes/{remote_name}/*")],
+            Some(&mut fetch_options),
+            Some(format!("Fetched from remote {remote_name}").as_str()),
+        )
+        .unwrap_or_else(|e| panic!("Fetching remote {remote_name} should not fail: {e}"));
+
+    let stats = remote.stats();
+
+    info!(
+        "Remote {remote_name}: {}/{} objects received ({} bytes), {} indexed, {} local objects reused",
+        stats.received_objects(),
+        stats.total_objects(),
+        stats.received_bytes(),
+        stats.indexed_objects(),
+        stats.local_objects()
+    );
+}
+
+fn conflicted_paths(repository: &Repository) -> Vec<String> {
+    repository
+        .index()
+        .unwrap()
+        .conflicts()
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect()
 }
@@ -1,17 +1,10 @@
-use std::env::var;
-use std::fs;
-
+use async_trait::async_trait;
 use log::debug;
-use octocrab::models::pulls::PullRequest;
-use octocrab::models::Repository;
 use octocrab::params::State;
 use octocrab::{Octocrab, OctocrabBuilder};
-use toml::Value;
 
-pub(crate) trait Github {
-    async fn get_repo(&self, owner: &str, repo: &str) -> Repository;
-    async fn get_all_my_open_prs(&self, owner: &str, repo: &str) -> Vec<PullRequest>;
-}
+use crate::forge::{Forge, ForgePullRequest, ForgeRef, ForgeRepo};
+use crate::token::resolve_token;
 
 pub(crate) struct GithubClient {
     octocrab: Octocrab,
@@ -26,6 +19,8 @@ impl GithubClient {
 }
 
 fn init_octocrab(host: &str) -> Octocrab {
+    let token = resolve_token(host).unwrap_or_else(|e| panic!("{e}"));
+
     OctocrabBuilder::new()
         .base_uri(if host == "github.com" {
             "https://api.github.com".to_string()
@@ -33,46 +28,26 @@ fn init_octocrab(host: &str) -> Octocrab {
             format!("https://{host}/api/v3")
         })
         .unwrap()
-        .personal_token(get_oauth_token(host))
+        .personal_token(token)
         .build()
         .unwrap()
 }
 
-fn get_oauth_token(host: &str) -> String {
-    let filename = format!("{}/.github", var("HOME").unwrap());
-
-    let config = fs::read_to_string(&filename)
-        .unwrap_or_else(|_| panic!("File {filename} is missing"))
-        .parse::<Value>()
-        .unwrap_or_else(|_| panic!("Error parsing {filename}"));
-
-    let config_table = config
-        .as_table()
-        .unwrap_or_else(|| panic!("Error parsing {filename}"));
-
-    let github_table = config_table
-        .get(host)
-        .unwrap_or_else(|| panic!("{host} table missing from {filename}"))
-        .as_table()
-        .unwrap_or_else(|| panic!("Error parsing table {host} from {filename}"));
-
-    github_table
-        .get("oauth")
-        .unwrap_or_else(|| panic!("Missing oauth key for {host} in {filename}"))
-        .as_str()
-        .unwrap_or_else(|| panic!("Expected string for oauth key under {host} in {filename}"))
-        .to_owned()
-}
-
-impl Github for GithubClient {
-    async fn get_repo(&self, owner: &str, repo: &str) -> Repository {
-        self.octocrab
+#[async_trait]
+impl Forge for GithubClient {
+    async fn get_repo(&self, owner: &str, repo: &str) -> ForgeRepo {
+        let repo: octocrab::models::Repository = self
+            .octocrab
             .get(format!("/repos/{owner}/{repo}"), None::<&()>)
             .await
-            .unwrap()
+            .unwrap();
+
+        ForgeRepo {
+            default_branch: repo.default_branch.unwrap(),
+        }
     }
 
-    async fn get_all_my_open_prs(&self, owner: &str, repo: &str) -> Vec<PullRequest> {
+    async fn get_all_open_prs(&self, owner: &str, repo: &str) -> Vec<ForgePullRequest> {
         let mut page = self
             .octocrab
             .pulls(owner, repo)
@@ -94,11 +69,22 @@ impl Github for GithubClient {
             all_prs.append(&mut page.items);
         }
 
-        let current_user_id = self.octocrab.current().user().await.unwrap().id;
-
         all_prs
             .into_iter()
-            .filter(|pr| pr.user.as_ref().unwrap().id == current_user_id)
+            .map(|pr| ForgePullRequest {
+                title: pr.title.unwrap(),
+                author: pr.user.unwrap().login,
+                head: ForgeRef {
+                    ref_field: pr.head.ref_field,
+                },
+                base: ForgeRef {
+                    ref_field: pr.base.ref_field,
+                },
+            })
             .collect()
     }
+
+    async fn get_current_user(&self) -> String {
+        self.octocrab.current().user().await.unwrap().login
+    }
 }
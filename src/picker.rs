@@ -0,0 +1,195 @@
+use std::io::{stdout, Write};
+
+use crossterm::cursor::MoveTo;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+
+use crate::forge::ForgePullRequest;
+
+struct Candidate {
+    index: usize,
+    label: String,
+    selected: bool,
+}
+
+/// Scores `candidate` against `query` as a subsequence match, or returns `None` if `query`
+/// is not a subsequence of `candidate`. Higher scores favor consecutive matches and matches
+/// at word boundaries; each run of unmatched characters before a matched character, including
+/// a leading run before the first match, is penalized once.
+pub(crate) fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let candidate_lower: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut score: i64 = 0;
+    let mut query_idx = 0;
+    let mut consecutive: i64 = 0;
+    let mut pending_gap = false;
+
+    for (i, &c) in candidate_lower.iter().enumerate() {
+        if query_idx == query.len() {
+            break;
+        }
+
+        if c != query[query_idx] {
+            consecutive = 0;
+            pending_gap = true;
+            continue;
+        }
+
+        consecutive += 1;
+        score += 1 + (consecutive - 1) * 5;
+
+        if is_word_boundary(&candidate_chars, i) {
+            score += 10;
+        }
+
+        if pending_gap {
+            score -= 2;
+            pending_gap = false;
+        }
+
+        query_idx += 1;
+    }
+
+    if query_idx < query.len() {
+        return None;
+    }
+
+    Some(score)
+}
+
+fn is_word_boundary(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+
+    let previous = chars[index - 1];
+    let current = chars[index];
+
+    matches!(previous, '-' | '_' | '/' | ' ') || (previous.is_lowercase() && current.is_uppercase())
+}
+
+/// Presents `prs` in a terminal list with live fuzzy filtering and lets the user toggle which
+/// ones to keep. All PRs are selected by default; pressing Esc deselects everything. Returns
+/// the indices (into `prs`) of the PRs that remained selected.
+pub(crate) fn pick_interactive(prs: &[ForgePullRequest]) -> Vec<usize> {
+    if prs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<Candidate> = prs
+        .iter()
+        .enumerate()
+        .map(|(index, pr)| Candidate {
+            index,
+            label: format!("{} ({})", pr.title, pr.head.ref_field),
+            selected: true,
+        })
+        .collect();
+
+    let mut stdout = stdout();
+    enable_raw_mode().expect("enabling raw mode should not fail");
+    execute!(stdout, EnterAlternateScreen).unwrap();
+
+    let mut query = String::new();
+    let mut cursor = 0usize;
+
+    loop {
+        let mut ranked: Vec<(usize, i64)> = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                fuzzy_score(&query, &candidate.label).map(|score| (i, score))
+            })
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        cursor = cursor.min(ranked.len().saturating_sub(1));
+
+        render(&mut stdout, &query, &candidates, &ranked, cursor);
+
+        let Event::Key(key) = event::read().unwrap() else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                candidates.iter_mut().for_each(|c| c.selected = false);
+                break;
+            }
+            KeyCode::Enter => break,
+            KeyCode::Char(' ') => {
+                if let Some(&(idx, _)) = ranked.get(cursor) {
+                    candidates[idx].selected = !candidates[idx].selected;
+                }
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                cursor = 0;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                cursor = 0;
+            }
+            KeyCode::Down => cursor = (cursor + 1).min(ranked.len().saturating_sub(1)),
+            KeyCode::Up => cursor = cursor.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    execute!(stdout, LeaveAlternateScreen).unwrap();
+    disable_raw_mode().expect("disabling raw mode should not fail");
+
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.selected)
+        .map(|candidate| candidate.index)
+        .collect()
+}
+
+fn render(
+    stdout: &mut impl Write,
+    query: &str,
+    candidates: &[Candidate],
+    ranked: &[(usize, i64)],
+    cursor: usize,
+) {
+    queue!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+    queue!(
+        stdout,
+        Print(format!("Filter (Esc to cancel, Enter to confirm): {query}")),
+        Print("\r\n")
+    )
+    .unwrap();
+
+    for (row, &(idx, _)) in ranked.iter().enumerate() {
+        let candidate = &candidates[idx];
+        let marker = if candidate.selected { "[x]" } else { "[ ]" };
+
+        if row == cursor {
+            queue!(stdout, SetForegroundColor(Color::Cyan)).unwrap();
+        }
+
+        queue!(
+            stdout,
+            Print(format!("{marker} {}", candidate.label)),
+            Print("\r\n"),
+            ResetColor
+        )
+        .unwrap();
+    }
+
+    stdout.flush().unwrap();
+}
@@ -0,0 +1,187 @@
+use std::env::var;
+
+use async_trait::async_trait;
+use git2::Oid;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::SmtpTransport;
+use lettre::{Message, Transport};
+use log::{error, info};
+
+pub(crate) enum PrOutcome {
+    SkippedUnsafe,
+    Conflict,
+    Pushed { commit: Oid },
+    PushFailed,
+}
+
+pub(crate) struct ReportEntry {
+    pub(crate) title: String,
+    pub(crate) base: String,
+    pub(crate) head: String,
+    pub(crate) outcome: PrOutcome,
+}
+
+#[async_trait]
+pub(crate) trait Reporter {
+    fn record(&mut self, entry: ReportEntry);
+
+    async fn finish(&mut self);
+}
+
+pub(crate) struct NullReporter;
+
+#[async_trait]
+impl Reporter for NullReporter {
+    fn record(&mut self, _entry: ReportEntry) {}
+
+    async fn finish(&mut self) {}
+}
+
+fn format_summary(entries: &[ReportEntry]) -> String {
+    if entries.is_empty() {
+        return "No PRs to report on.".to_string();
+    }
+
+    entries
+        .iter()
+        .map(|entry| {
+            let status = match &entry.outcome {
+                PrOutcome::SkippedUnsafe => "skipped (unsafe)".to_string(),
+                PrOutcome::Conflict => "conflict".to_string(),
+                PrOutcome::Pushed { commit } => format!("pushed {commit}"),
+                PrOutcome::PushFailed => "push failed".to_string(),
+            };
+
+            format!(
+                "\"{}\" {} <- {}: {status}",
+                entry.title, entry.base, entry.head
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) struct WebhookReporter {
+    url: String,
+    entries: Vec<ReportEntry>,
+}
+
+impl WebhookReporter {
+    pub(crate) fn new(url: String) -> WebhookReporter {
+        WebhookReporter {
+            url,
+            entries: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Reporter for WebhookReporter {
+    fn record(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    async fn finish(&mut self) {
+        let summary = format_summary(&self.entries);
+
+        let response = reqwest::Client::new()
+            .post(&self.url)
+            .header("Content-Type", "text/plain")
+            .body(summary)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                info!("Posted rebase summary to webhook");
+            }
+            Ok(response) => {
+                error!("Webhook {} returned {}", self.url, response.status());
+            }
+            Err(e) => {
+                error!("Failed to post rebase summary to webhook {}: {e}", self.url);
+            }
+        }
+    }
+}
+
+pub(crate) struct EmailReporter {
+    smtp_host: String,
+    from: String,
+    recipients: Vec<String>,
+    entries: Vec<ReportEntry>,
+}
+
+impl EmailReporter {
+    pub(crate) fn new(smtp_host: String, from: String, recipients: Vec<String>) -> EmailReporter {
+        EmailReporter {
+            smtp_host,
+            from,
+            recipients,
+            entries: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Reporter for EmailReporter {
+    fn record(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    async fn finish(&mut self) {
+        let summary = format_summary(&self.entries);
+        let smtp_host = self.smtp_host.clone();
+        let from = self.from.clone();
+        let recipients = self.recipients.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mut builder = Message::builder()
+                .from(
+                    from.parse::<Mailbox>()
+                        .expect("from address should be valid"),
+                )
+                .subject("rebaser: PR summary");
+
+            for recipient in &recipients {
+                builder = builder.to(recipient
+                    .parse::<Mailbox>()
+                    .expect("recipient address should be valid"));
+            }
+
+            let message = builder
+                .body(summary)
+                .expect("building the email body should not fail");
+
+            let mailer = SmtpTransport::relay(&smtp_host)
+                .expect("building the SMTP transport should not fail")
+                .build();
+
+            mailer.send(&message)
+        })
+        .await
+        .expect("email task should not panic");
+
+        match result {
+            Ok(_) => info!("Emailed rebase summary to {}", self.recipients.join(", ")),
+            Err(e) => error!("Failed to email rebase summary: {e}"),
+        }
+    }
+}
+
+pub(crate) fn reporter_from_env() -> Box<dyn Reporter> {
+    if let Ok(url) = var("REBASER_REPORT_WEBHOOK_URL") {
+        return Box::new(WebhookReporter::new(url));
+    }
+
+    if let (Ok(smtp_host), Ok(from), Ok(to)) = (
+        var("REBASER_REPORT_SMTP_HOST"),
+        var("REBASER_REPORT_EMAIL_FROM"),
+        var("REBASER_REPORT_EMAIL_TO"),
+    ) {
+        let recipients = to.split(',').map(str::trim).map(str::to_string).collect();
+        return Box::new(EmailReporter::new(smtp_host, from, recipients));
+    }
+
+    Box::new(NullReporter)
+}
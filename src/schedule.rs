@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use log::info;
+
+use crate::forge::ForgePullRequest;
+use crate::git::RepositoryOps;
+
+pub(crate) struct ScheduledPr<'a> {
+    pub(crate) pr: &'a ForgePullRequest,
+    pub(crate) base: String,
+}
+
+/// Picks the branch `pr` should actually be rebased onto. Prefers the forge-reported base,
+/// but if the base has already been merged into exactly one other open PR's head (the base
+/// is an ancestor of that head, which is itself an ancestor of `pr`'s head), that head is
+/// used instead, since the API's base hasn't caught up with the local stack yet.
+fn resolve_effective_base(
+    repo: &impl RepositoryOps,
+    pr: &ForgePullRequest,
+    all_prs: &[ForgePullRequest],
+) -> String {
+    let declared_base = &pr.base.ref_field;
+
+    let candidates: Vec<&ForgePullRequest> = all_prs
+        .iter()
+        .filter(|other| other.head.ref_field != pr.head.ref_field)
+        .filter(|other| repo.is_ancestor(declared_base, &other.head.ref_field))
+        .filter(|other| repo.is_ancestor(&other.head.ref_field, &pr.head.ref_field))
+        .collect();
+
+    match candidates.as_slice() {
+        [] => declared_base.clone(),
+        [only] if only.head.ref_field != *declared_base => {
+            info!(
+                "\"{}\" reports base \"{declared_base}\", but local history shows it is already merged into \"{}\"; rebasing onto that instead",
+                pr.title, only.head.ref_field
+            );
+            only.head.ref_field.clone()
+        }
+        [only] => only.head.ref_field.clone(),
+        candidates => {
+            info!(
+                "\"{}\" base \"{declared_base}\" disagrees with local history, but {} branches could be its true parent; keeping the reported base",
+                pr.title,
+                candidates.len()
+            );
+            declared_base.clone()
+        }
+    }
+}
+
+pub(crate) fn topological_order<'a>(
+    repo: &impl RepositoryOps,
+    prs: &'a [ForgePullRequest],
+    default_branch: &str,
+) -> Result<Vec<ScheduledPr<'a>>, Vec<&'a ForgePullRequest>> {
+    let effective_bases: HashMap<&str, String> = prs
+        .iter()
+        .map(|pr| {
+            (
+                pr.head.ref_field.as_str(),
+                resolve_effective_base(repo, pr, prs),
+            )
+        })
+        .collect();
+
+    let mut children_by_base: HashMap<&str, Vec<&ForgePullRequest>> = HashMap::new();
+
+    for pr in prs {
+        let base = effective_bases[pr.head.ref_field.as_str()].as_str();
+        children_by_base.entry(base).or_default().push(pr);
+    }
+
+    let mut order = Vec::new();
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back(default_branch);
+
+    while let Some(base) = queue.pop_front() {
+        if !visited.insert(base) {
+            continue;
+        }
+
+        let Some(children) = children_by_base.get(base) else {
+            continue;
+        };
+
+        for pr in children {
+            let base = effective_bases[pr.head.ref_field.as_str()].clone();
+
+            order.push(ScheduledPr { pr, base });
+            queue.push_back(pr.head.ref_field.as_str());
+        }
+    }
+
+    if order.len() < prs.len() {
+        let unreachable = prs
+            .iter()
+            .filter(|pr| {
+                !order
+                    .iter()
+                    .any(|scheduled| std::ptr::eq(scheduled.pr, *pr))
+            })
+            .collect();
+
+        return Err(unreachable);
+    }
+
+    Ok(order)
+}
@@ -0,0 +1,53 @@
+use std::io::{stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Prints a spinner with `message` on a background thread until dropped, to give feedback
+/// during long-running phases (fetch, rebase, push) that would otherwise look stalled.
+pub(crate) struct Spinner {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    pub(crate) fn start(message: &str) -> Spinner {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = running.clone();
+        let message = message.to_string();
+
+        let handle = std::thread::spawn(move || {
+            let mut stdout = stdout();
+            let mut frame = 0usize;
+
+            while running_clone.load(Ordering::Relaxed) {
+                print!("\r{} {message}", FRAMES[frame % FRAMES.len()]);
+                stdout.flush().unwrap();
+
+                frame += 1;
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            print!("\r{}\r", " ".repeat(message.len() + 2));
+            stdout.flush().unwrap();
+        });
+
+        Spinner {
+            running,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            handle.join().expect("spinner thread should not panic");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::env::var;
+use std::fmt;
+use std::fs;
+
+use toml::Value;
+
+#[derive(Debug)]
+pub(crate) enum TokenError {
+    NotFound { host: String },
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::NotFound { host } => write!(
+                f,
+                "No GitHub token found for {host}. Set GITHUB_TOKEN (or a host-specific \
+                 <HOST>_TOKEN), run `gh auth login`, or add an oauth key for {host} to ~/.github"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Resolves a GitHub token for `host`, trying in order: `GITHUB_TOKEN`/`<HOST>_TOKEN`
+/// environment variables, the `gh` CLI's `hosts.yml`, then the `~/.github` TOML file.
+pub(crate) fn resolve_token(host: &str) -> Result<String, TokenError> {
+    token_from_env(host)
+        .or_else(|| token_from_gh_cli(host))
+        .or_else(|| token_from_github_file(host))
+        .ok_or_else(|| TokenError::NotFound {
+            host: host.to_string(),
+        })
+}
+
+fn token_from_env(host: &str) -> Option<String> {
+    if let Ok(token) = var("GITHUB_TOKEN") {
+        return Some(token);
+    }
+
+    let host_var = format!(
+        "{}_TOKEN",
+        host.to_uppercase().replace(['.', '-'], "_")
+    );
+
+    var(host_var).ok()
+}
+
+fn token_from_gh_cli(host: &str) -> Option<String> {
+    let home = var("HOME").ok()?;
+    let contents = fs::read_to_string(format!("{home}/.config/gh/hosts.yml")).ok()?;
+
+    let mut in_host_block = false;
+
+    for line in contents.lines() {
+        if !line.starts_with([' ', '\t']) {
+            in_host_block = line.trim_end().trim_end_matches(':') == host;
+            continue;
+        }
+
+        if !in_host_block {
+            continue;
+        }
+
+        if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+
+    None
+}
+
+fn token_from_github_file(host: &str) -> Option<String> {
+    let home = var("HOME").ok()?;
+    let config = fs::read_to_string(format!("{home}/.github"))
+        .ok()?
+        .parse::<Value>()
+        .ok()?;
+
+    config
+        .as_table()?
+        .get(host)?
+        .as_table()?
+        .get("oauth")?
+        .as_str()
+        .map(str::to_string)
+}